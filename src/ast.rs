@@ -1,65 +1,233 @@
+use num_bigint::BigInt;
+
+/// A byte-offset range into the source text a node was parsed from.
+pub type Span = (usize, usize);
+
+/// Wraps an AST node with the span of source it came from.
+///
+/// Derefs to the wrapped node so method calls on `T`, and passing a
+/// `Spanned<T>` where a `&T` is expected, keep working; pattern matches
+/// go through the `node` field.
+///
+/// With the `serde` feature enabled, every node in this module
+/// (de)serializes so a parsed module can round-trip through JSON — e.g.
+/// for golden-file grammar tests or for tooling that wants the syntax
+/// tree without linking the lalrpop-generated parser.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spanned<T> {
+    pub node: T,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, start: usize, end: usize) -> Self {
+        Spanned { node, start, end }
+    }
+
+    pub fn span(&self) -> Span {
+        (self.start, self.end)
+    }
+}
+
+impl<T> std::ops::Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.node
+    }
+}
+
+pub type SExpr = Spanned<Expr>;
+pub type SStatement = Spanned<Statement>;
+
+/// A single Starlark statement, simple or compound.
 #[derive(Debug, Clone)]
-pub struct Statement;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Statement {
+    Def {
+        name: String,
+        params: Vec<Param>,
+        body: Suite,
+    },
+    If {
+        /// `if`/`elif` conditions paired with their bodies, in order.
+        branches: Vec<(SExpr, Suite)>,
+        orelse: Option<Suite>,
+    },
+    For {
+        targets: Vec<SExpr>,
+        iter: Box<SExpr>,
+        body: Suite,
+    },
+    Return(Option<Box<SExpr>>),
+    Break,
+    Continue,
+    Pass,
+    Load {
+        module: String,
+        bindings: Vec<LoadBinding>,
+    },
+    Assign {
+        // `a = b = c` binds the same value to every target in turn.
+        targets: Vec<SExpr>,
+        value: Box<SExpr>,
+    },
+    AugAssign {
+        target: Box<SExpr>,
+        op: AugOp,
+        value: Box<SExpr>,
+    },
+    Expr(Box<SExpr>),
+}
+
+/// One parameter of a `def`: `name`, `name=default`, `*args`, or `**kwargs`.
 #[derive(Debug, Clone)]
-pub struct SimpleStmt;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Param {
+    pub name: String,
+    pub default: Option<SExpr>,
+    pub kind: ParamKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParamKind {
+    Normal,
+    Args,
+    Kwargs,
+}
+
+/// One `local = exported` binding inside a `load(...)` statement; for
+/// `load("m", "x")` `local` and `exported` are both `"x"`.
 #[derive(Debug, Clone)]
-pub enum Test {
-    Nil,
-    IfExpr { cond: Box<Test>, alt: Box<Test> },
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadBinding {
+    pub local: String,
+    pub exported: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AugOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FloorDiv,
+    Mod,
 }
 
+/// A simple-statement suite packed onto one line, e.g. the `pass` in
+/// `if x: pass` or the `a; b` in `if x: a; b`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimpleStmt(pub Vec<SStatement>);
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suite {
-    Statements(Vec<Statement>),
+    Statements(Vec<SStatement>),
     SimpleStmt(SimpleStmt),
 }
 
+/// What's inside `[...]` after a primary expression: a single index
+/// (`a[i]`) or a `start:stop:step` range, any component of which may be
+/// omitted (`a[1:]`, `a[:2]`, `a[::2]`, ...).
 #[derive(Debug, Clone)]
-pub struct Slice;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slice {
+    pub index: SliceIndex,
+}
+
 #[derive(Debug, Clone)]
-pub struct Call;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SliceIndex {
+    Index(Box<SExpr>),
+    Range { start: Option<Box<SExpr>>, stop: Option<Box<SExpr>>, step: Option<Box<SExpr>> },
+}
 
 #[derive(Debug, Clone)]
-pub struct Tuple(pub Vec<Expr>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Call {
+    pub args: Vec<Argument>,
+}
+
+/// One argument in a call: `f(a, b=2, *c, **d)`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Argument {
+    Positional(SExpr),
+    Keyword(String, SExpr),
+    Args(SExpr),
+    Kwargs(SExpr),
+}
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     // Binary
-    Or(Box<Expr>, Box<Expr>),
-    And(Box<Expr>, Box<Expr>),
-    Eq(Box<Expr>, Box<Expr>),
-    Ne(Box<Expr>, Box<Expr>),
-    Lt(Box<Expr>, Box<Expr>),
-    Gt(Box<Expr>, Box<Expr>),
-    Le(Box<Expr>, Box<Expr>),
-    Ge(Box<Expr>, Box<Expr>),
-    In(Box<Expr>, Box<Expr>),
-    NotIn(Box<Expr>, Box<Expr>),
-    BitOr(Box<Expr>, Box<Expr>),
-    BitAnd(Box<Expr>, Box<Expr>),
-    Sub(Box<Expr>, Box<Expr>),
-    Add(Box<Expr>, Box<Expr>),
-    Mul(Box<Expr>, Box<Expr>),
-    Mod(Box<Expr>, Box<Expr>),
-    Div(Box<Expr>, Box<Expr>),
-    DivFloor(Box<Expr>, Box<Expr>),
+    Or(Box<SExpr>, Box<SExpr>),
+    And(Box<SExpr>, Box<SExpr>),
+    Eq(Box<SExpr>, Box<SExpr>),
+    Ne(Box<SExpr>, Box<SExpr>),
+    Lt(Box<SExpr>, Box<SExpr>),
+    Gt(Box<SExpr>, Box<SExpr>),
+    Le(Box<SExpr>, Box<SExpr>),
+    Ge(Box<SExpr>, Box<SExpr>),
+    In(Box<SExpr>, Box<SExpr>),
+    NotIn(Box<SExpr>, Box<SExpr>),
+    BitOr(Box<SExpr>, Box<SExpr>),
+    BitAnd(Box<SExpr>, Box<SExpr>),
+    Sub(Box<SExpr>, Box<SExpr>),
+    Add(Box<SExpr>, Box<SExpr>),
+    Mul(Box<SExpr>, Box<SExpr>),
+    Mod(Box<SExpr>, Box<SExpr>),
+    Div(Box<SExpr>, Box<SExpr>),
+    DivFloor(Box<SExpr>, Box<SExpr>),
 
     // Unary
-    Neg(Box<Expr>),
-    Not(Box<Expr>),
+    Neg(Box<SExpr>),
+    Not(Box<SExpr>),
 
     // Special
-    Dot(Box<Expr>, String),
-    Slice(Box<Expr>, Box<Slice>),
-    Call(Box<Expr>, Box<Call>),
+    Dot(Box<SExpr>, String),
+    Slice(Box<SExpr>, Box<Slice>),
+    Call(Box<SExpr>, Box<Call>),
 
     // Operands
     Identifier(String),
-    Int(i32),
-    String(Vec<u8>),
-    Tuple(Vec<Expr>),
-    ListExpr(Vec<Expr>),
-    ListComp,
-    DictExpr,
-    DictComp,
+    Bool(bool),
+    None,
+    // Starlark integers are arbitrary precision; `BigInt` keeps literals
+    // like `2**256` from silently wrapping the way `i32`/`i64` would.
+    // (De)serializing this variant needs num-bigint's own `serde`
+    // feature enabled alongside ours.
+    Int(BigInt),
+    // `"..."`/`'...'` (including triple-quoted and raw forms) decode to
+    // UTF-8 text; `b"..."` keeps the raw bytes instead.
+    String(String),
+    Bytes(Vec<u8>),
+    Tuple(Vec<SExpr>),
+    List(Vec<SExpr>),
+    ListComp {
+        element: Box<SExpr>,
+        clauses: Vec<CompClause>,
+    },
+    Dict(Vec<(SExpr, SExpr)>),
+    DictComp {
+        key: Box<SExpr>,
+        value: Box<SExpr>,
+        clauses: Vec<CompClause>,
+    },
+}
+
+/// One `for`/`if` clause of a list or dict comprehension, e.g. the
+/// `for x in seq` and `if cond` in `[x for x in seq if cond]`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompClause {
+    For { targets: Vec<SExpr>, iter: Box<SExpr> },
+    If(Box<SExpr>),
 }
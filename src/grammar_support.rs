@@ -0,0 +1,65 @@
+//! Helper functions for `skylark.lalrpop`'s action code.
+//!
+//! LALRPOP grammar files only accept `use` imports, token/extern
+//! declarations, and nonterminal rules at the top level — unlike a plain
+//! Rust module, a `.lalrpop` file can't hold `fn` items of its own. Any
+//! Rust logic a grammar action needs has to live here instead and get
+//! pulled in with a `use`.
+
+use num_bigint::BigInt;
+
+use crate::ast::{CompClause, Expr, SExpr, Spanned};
+
+pub(crate) fn spanned(expr: Expr, start: usize, end: usize) -> Box<SExpr> {
+    Box::new(Spanned::new(expr, start, end))
+}
+
+// Every grammar action builds individual elements as `Box<SExpr>` (that's
+// what `spanned` returns), so the `Vec<Box<SExpr>>` callers pass in here
+// isn't gratuitous boxing to unwrap.
+#[allow(clippy::vec_box)]
+pub(crate) fn into_nodes(items: Vec<Box<SExpr>>) -> Vec<SExpr> {
+    items.into_iter().map(|b| *b).collect()
+}
+
+/// Parses the decimal/octal/hex/binary literal forms lalrpop's `"int"`
+/// token covers, including `_` digit separators; `0`/leading-zero rules
+/// match Starlark's grammar. Magnitude is unbounded since `BigInt` never
+/// overflows.
+pub(crate) fn parse_int(s: &str) -> Result<BigInt, ()> {
+    let cleaned: String = s.chars().filter(|&c| c != '_').collect();
+    if cleaned.bytes().all(|b| b == b'0') {
+        return Ok(BigInt::from(0));
+    }
+    let lower = cleaned.to_ascii_lowercase();
+    if let Some(rest) = lower.strip_prefix("0x") {
+        return BigInt::parse_bytes(rest.as_bytes(), 16).ok_or(());
+    }
+    if let Some(rest) = lower.strip_prefix("0o") {
+        return BigInt::parse_bytes(rest.as_bytes(), 8).ok_or(());
+    }
+    if let Some(rest) = lower.strip_prefix("0b") {
+        return BigInt::parse_bytes(rest.as_bytes(), 2).ok_or(());
+    }
+    if cleaned.starts_with('0') {
+        return Err(());
+    }
+    BigInt::parse_bytes(cleaned.as_bytes(), 10).ok_or(())
+}
+
+/// What follows a dict display/comprehension's first `key: value` pair —
+/// split out so the grammar can decide between the two forms on the
+/// lookahead token (`,`/`}` vs `for`/`if`) instead of trying to keep
+/// reducing and shifting the shared `Expr ":" Expr` prefix at once.
+pub(crate) enum DictTail {
+    Display(Vec<(SExpr, SExpr)>),
+    Comp(Vec<CompClause>),
+}
+
+/// The list-display counterpart of `DictTail`, for `[x, y]` vs.
+/// `[x for x in y]`.
+#[allow(clippy::vec_box)]
+pub(crate) enum ListTail {
+    Display(Vec<Box<SExpr>>),
+    Comp(Vec<CompClause>),
+}
@@ -0,0 +1,813 @@
+// This tree-walking evaluator is exercised directly by its own unit tests
+// below; it isn't wired to a public entry point yet, so rustc can't see
+// those call sites and flags everything as dead without this.
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use num_bigint::{BigInt, Sign};
+
+use crate::ast::{CompClause, Expr, SExpr, SliceIndex};
+
+/// A runtime Starlark value.
+///
+/// There is no `Function` variant: `def` statements are not evaluated,
+/// so nothing produces a callable value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    None,
+    Bool(bool),
+    Int(BigInt),
+    String(String),
+    Bytes(Vec<u8>),
+    Tuple(Vec<Value>),
+    List(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+}
+
+impl Value {
+    /// Starlark truthiness: `None`, `False`, `0`, and empty strings/
+    /// collections are falsy; everything else is truthy.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::None => false,
+            Value::Bool(b) => *b,
+            Value::Int(i) => i.sign() != Sign::NoSign,
+            Value::String(s) => !s.is_empty(),
+            Value::Bytes(b) => !b.is_empty(),
+            Value::Tuple(v) => !v.is_empty(),
+            Value::List(v) => !v.is_empty(),
+            Value::Dict(v) => !v.is_empty(),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::None => "NoneType",
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            Value::String(_) => "string",
+            Value::Bytes(_) => "bytes",
+            Value::Tuple(_) => "tuple",
+            Value::List(_) => "list",
+            Value::Dict(_) => "dict",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalError {
+    NameError(String),
+    TypeError(String),
+    DivisionByZero,
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::NameError(name) => write!(f, "name '{}' is not defined", name),
+            EvalError::TypeError(msg) => write!(f, "{}", msg),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Unsupported(what) => write!(f, "{} is not supported yet", what),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// A chain of lexical scopes. `eval` looks a name up from the innermost
+/// scope outward, and assignments always write to the innermost one.
+pub struct Env {
+    scopes: Vec<HashMap<String, Value>>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Env {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.scopes
+            .last_mut()
+            .expect("Env always has at least one scope")
+            .insert(name.to_string(), value);
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Env::new()
+    }
+}
+
+pub fn eval(expr: &Expr, env: &mut Env) -> Result<Value, EvalError> {
+    match expr {
+        Expr::Or(lhs, rhs) => {
+            let lhs = eval(lhs, env)?;
+            if lhs.is_truthy() {
+                Ok(lhs)
+            } else {
+                eval(rhs, env)
+            }
+        }
+        Expr::And(lhs, rhs) => {
+            let lhs = eval(lhs, env)?;
+            if lhs.is_truthy() {
+                eval(rhs, env)
+            } else {
+                Ok(lhs)
+            }
+        }
+
+        Expr::Eq(lhs, rhs) => Ok(Value::Bool(eval(lhs, env)? == eval(rhs, env)?)),
+        Expr::Ne(lhs, rhs) => Ok(Value::Bool(eval(lhs, env)? != eval(rhs, env)?)),
+        Expr::Lt(lhs, rhs) => compare(lhs, rhs, env).map(|o| Value::Bool(o == Ordering::Less)),
+        Expr::Gt(lhs, rhs) => compare(lhs, rhs, env).map(|o| Value::Bool(o == Ordering::Greater)),
+        Expr::Le(lhs, rhs) => compare(lhs, rhs, env).map(|o| Value::Bool(o != Ordering::Greater)),
+        Expr::Ge(lhs, rhs) => compare(lhs, rhs, env).map(|o| Value::Bool(o != Ordering::Less)),
+
+        Expr::In(lhs, rhs) => {
+            let needle = eval(lhs, env)?;
+            let haystack = eval(rhs, env)?;
+            contains(&haystack, &needle).map(Value::Bool)
+        }
+        Expr::NotIn(lhs, rhs) => {
+            let needle = eval(lhs, env)?;
+            let haystack = eval(rhs, env)?;
+            contains(&haystack, &needle).map(|b| Value::Bool(!b))
+        }
+
+        Expr::BitOr(lhs, rhs) => int_op(lhs, rhs, env, "|", |a, b| a | b),
+        Expr::BitAnd(lhs, rhs) => int_op(lhs, rhs, env, "&", |a, b| a & b),
+
+        Expr::Add(lhs, rhs) => add(eval(lhs, env)?, eval(rhs, env)?),
+        Expr::Sub(lhs, rhs) => int_op(lhs, rhs, env, "-", |a, b| a - b),
+        Expr::Mul(lhs, rhs) => int_op(lhs, rhs, env, "*", |a, b| a * b),
+        Expr::Mod(lhs, rhs) => checked_int_op(lhs, rhs, env, "%", mod_floor),
+        Expr::Div(lhs, rhs) => checked_int_op(lhs, rhs, env, "/", |a, b| &a / &b),
+        Expr::DivFloor(lhs, rhs) => checked_int_op(lhs, rhs, env, "//", div_floor),
+
+        Expr::Neg(operand) => match eval(operand, env)? {
+            Value::Int(i) => Ok(Value::Int(-i)),
+            other => Err(EvalError::TypeError(format!(
+                "unsupported operand type for unary -: '{}'",
+                other.type_name()
+            ))),
+        },
+        Expr::Not(operand) => Ok(Value::Bool(!eval(operand, env)?.is_truthy())),
+
+        Expr::Dot(operand, _name) => {
+            // No Value variant carries attributes yet (there's nothing in
+            // this interpreter with methods or fields to look up), so this
+            // can't do real work until one does.
+            eval(operand, env)?;
+            Err(EvalError::Unsupported("attribute access"))
+        }
+        Expr::Slice(operand, slice) => {
+            let target = eval(operand, env)?;
+            match &slice.index {
+                SliceIndex::Index(index) => {
+                    let index = eval(index, env)?;
+                    index_value(&target, &index)
+                }
+                SliceIndex::Range { start, stop, step } => {
+                    let start = start.as_deref().map(|e| eval(e, env)).transpose()?;
+                    let stop = stop.as_deref().map(|e| eval(e, env)).transpose()?;
+                    let step = step.as_deref().map(|e| eval(e, env)).transpose()?;
+                    slice_range(&target, start, stop, step)
+                }
+            }
+        }
+        Expr::Call(operand, call) => {
+            // No Value variant represents a callable yet (`def` isn't
+            // evaluated into one), so there's nothing to invoke here yet.
+            eval(operand, env)?;
+            for arg in &call.args {
+                match arg {
+                    crate::ast::Argument::Positional(e)
+                    | crate::ast::Argument::Keyword(_, e)
+                    | crate::ast::Argument::Args(e)
+                    | crate::ast::Argument::Kwargs(e) => {
+                        eval(e, env)?;
+                    }
+                }
+            }
+            Err(EvalError::Unsupported("calls"))
+        }
+
+        Expr::Identifier(name) => env
+            .get(name)
+            .cloned()
+            .ok_or_else(|| EvalError::NameError(name.clone())),
+        Expr::Bool(b) => Ok(Value::Bool(*b)),
+        Expr::None => Ok(Value::None),
+        Expr::Int(i) => Ok(Value::Int(i.clone())),
+        Expr::String(s) => Ok(Value::String(s.clone())),
+        Expr::Bytes(b) => Ok(Value::Bytes(b.clone())),
+        Expr::Tuple(items) => items
+            .iter()
+            .map(|e| eval(e, env))
+            .collect::<Result<_, _>>()
+            .map(Value::Tuple),
+        Expr::List(items) => items
+            .iter()
+            .map(|e| eval(e, env))
+            .collect::<Result<_, _>>()
+            .map(Value::List),
+
+        Expr::ListComp { element, clauses } => {
+            eval_comp(element, clauses, env).map(Value::List)
+        }
+        Expr::Dict(entries) => entries
+            .iter()
+            .map(|(k, v)| Ok((eval(k, env)?, eval(v, env)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Dict),
+        Expr::DictComp { key, value, clauses } => {
+            eval_dict_comp(key, value, clauses, env).map(Value::Dict)
+        }
+    }
+}
+
+/// Evaluates `element` once per binding produced by `clauses`, in
+/// order, recursing one clause at a time so nested `for`s and `if`s
+/// compose the way Python/Starlark comprehensions do.
+fn eval_comp(element: &Expr, clauses: &[CompClause], env: &mut Env) -> Result<Vec<Value>, EvalError> {
+    match clauses.split_first() {
+        None => Ok(vec![eval(element, env)?]),
+        Some((CompClause::If(cond), rest)) => {
+            if eval(cond, env)?.is_truthy() {
+                eval_comp(element, rest, env)
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        Some((CompClause::For { targets, iter }, rest)) => {
+            let mut results = Vec::new();
+            for item in iter_values(&eval(iter, env)?)? {
+                env.push_scope();
+                let bound = bind_targets(targets, item, env).and_then(|()| eval_comp(element, rest, env));
+                env.pop_scope();
+                results.extend(bound?);
+            }
+            Ok(results)
+        }
+    }
+}
+
+fn eval_dict_comp(
+    key: &Expr,
+    value: &Expr,
+    clauses: &[CompClause],
+    env: &mut Env,
+) -> Result<Vec<(Value, Value)>, EvalError> {
+    match clauses.split_first() {
+        None => Ok(vec![(eval(key, env)?, eval(value, env)?)]),
+        Some((CompClause::If(cond), rest)) => {
+            if eval(cond, env)?.is_truthy() {
+                eval_dict_comp(key, value, rest, env)
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        Some((CompClause::For { targets, iter }, rest)) => {
+            let mut results = Vec::new();
+            for item in iter_values(&eval(iter, env)?)? {
+                env.push_scope();
+                let bound =
+                    bind_targets(targets, item, env).and_then(|()| eval_dict_comp(key, value, rest, env));
+                env.pop_scope();
+                results.extend(bound?);
+            }
+            Ok(results)
+        }
+    }
+}
+
+/// The elements a `for` clause iterates over: a dict yields its keys,
+/// a string its characters, bytes their integer byte values.
+fn iter_values(v: &Value) -> Result<Vec<Value>, EvalError> {
+    match v {
+        Value::List(items) | Value::Tuple(items) => Ok(items.clone()),
+        Value::Dict(entries) => Ok(entries.iter().map(|(k, _)| k.clone()).collect()),
+        Value::String(s) => Ok(s.chars().map(|c| Value::String(c.to_string())).collect()),
+        Value::Bytes(b) => Ok(b.iter().map(|&byte| Value::Int(BigInt::from(byte))).collect()),
+        other => Err(EvalError::TypeError(format!(
+            "'{}' object is not iterable",
+            other.type_name()
+        ))),
+    }
+}
+
+fn bind_targets(targets: &[SExpr], value: Value, env: &mut Env) -> Result<(), EvalError> {
+    if let [target] = targets {
+        return bind_target(target, value, env);
+    }
+    let items = match value {
+        Value::Tuple(items) | Value::List(items) => items,
+        other => {
+            return Err(EvalError::TypeError(format!(
+                "cannot unpack non-sequence '{}'",
+                other.type_name()
+            )))
+        }
+    };
+    if items.len() != targets.len() {
+        return Err(EvalError::TypeError(
+            "wrong number of values to unpack".to_string(),
+        ));
+    }
+    for (target, item) in targets.iter().zip(items) {
+        bind_target(target, item, env)?;
+    }
+    Ok(())
+}
+
+fn bind_target(target: &Expr, value: Value, env: &mut Env) -> Result<(), EvalError> {
+    match target {
+        Expr::Identifier(name) => {
+            env.set(name, value);
+            Ok(())
+        }
+        _ => Err(EvalError::Unsupported("destructuring comprehension targets")),
+    }
+}
+
+fn compare(lhs: &Expr, rhs: &Expr, env: &mut Env) -> Result<Ordering, EvalError> {
+    let lhs = eval(lhs, env)?;
+    let rhs = eval(rhs, env)?;
+    match (&lhs, &rhs) {
+        (Value::Int(a), Value::Int(b)) => Ok(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+        (Value::Bytes(a), Value::Bytes(b)) => Ok(a.cmp(b)),
+        (Value::Tuple(a), Value::List(b)) | (Value::List(a), Value::Tuple(b)) => {
+            Err(EvalError::TypeError(format!(
+                "unsupported comparison: {} vs {}",
+                Value::List(a.clone()).type_name(),
+                Value::List(b.clone()).type_name()
+            )))
+        }
+        _ if lhs.type_name() == rhs.type_name() => Err(EvalError::Unsupported("ordering this type")),
+        _ => Err(EvalError::TypeError(format!(
+            "unsupported comparison: {} vs {}",
+            lhs.type_name(),
+            rhs.type_name()
+        ))),
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> Result<bool, EvalError> {
+    match haystack {
+        Value::List(items) | Value::Tuple(items) => Ok(items.contains(needle)),
+        Value::Dict(entries) => Ok(entries.iter().any(|(k, _)| k == needle)),
+        Value::String(s) => match needle {
+            Value::String(n) => Ok(s.contains(n.as_str())),
+            _ => Err(EvalError::TypeError(
+                "'in <string>' requires string as left operand".to_string(),
+            )),
+        },
+        Value::Bytes(b) => match needle {
+            Value::Bytes(n) => Ok(windows_contain(b, n)),
+            _ => Err(EvalError::TypeError(
+                "'in <bytes>' requires bytes as left operand".to_string(),
+            )),
+        },
+        other => Err(EvalError::TypeError(format!(
+            "argument of type '{}' is not iterable",
+            other.type_name()
+        ))),
+    }
+}
+
+fn windows_contain(haystack: &[u8], needle: &[u8]) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// `a[i]`: a dict key lookup, or an integer index (negative counts from
+/// the end) into a list/tuple/string/bytes.
+fn index_value(target: &Value, index: &Value) -> Result<Value, EvalError> {
+    match target {
+        Value::Dict(entries) => entries
+            .iter()
+            .find(|(k, _)| k == index)
+            .map(|(_, v)| v.clone())
+            .ok_or(EvalError::TypeError("key not found".to_string())),
+        Value::List(items) | Value::Tuple(items) => {
+            let i = normalize_index(to_index(index)?, items.len())?;
+            Ok(items[i].clone())
+        }
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            let i = normalize_index(to_index(index)?, chars.len())?;
+            Ok(Value::String(chars[i].to_string()))
+        }
+        Value::Bytes(b) => {
+            let i = normalize_index(to_index(index)?, b.len())?;
+            Ok(Value::Int(BigInt::from(b[i])))
+        }
+        other => Err(EvalError::TypeError(format!(
+            "'{}' object is not subscriptable",
+            other.type_name()
+        ))),
+    }
+}
+
+/// `a[start:stop:step]`, with Python/Starlark's clamping semantics: any
+/// component may be missing, indices may run past either end, and a
+/// negative `step` reverses the walk (making `start`/`stop` default to
+/// the last/first element rather than the first/last).
+fn slice_range(
+    target: &Value,
+    start: Option<Value>,
+    stop: Option<Value>,
+    step: Option<Value>,
+) -> Result<Value, EvalError> {
+    let step = step.as_ref().map(to_index).transpose()?.unwrap_or(1);
+    if step == 0 {
+        return Err(EvalError::TypeError("slice step cannot be zero".to_string()));
+    }
+    let start = start.as_ref().map(to_index).transpose()?;
+    let stop = stop.as_ref().map(to_index).transpose()?;
+
+    match target {
+        Value::List(items) => {
+            let indices = slice_indices(items.len(), start, stop, step);
+            Ok(Value::List(indices.into_iter().map(|i| items[i].clone()).collect()))
+        }
+        Value::Tuple(items) => {
+            let indices = slice_indices(items.len(), start, stop, step);
+            Ok(Value::Tuple(indices.into_iter().map(|i| items[i].clone()).collect()))
+        }
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            let indices = slice_indices(chars.len(), start, stop, step);
+            Ok(Value::String(indices.into_iter().map(|i| chars[i]).collect()))
+        }
+        Value::Bytes(b) => {
+            let indices = slice_indices(b.len(), start, stop, step);
+            Ok(Value::Bytes(indices.into_iter().map(|i| b[i]).collect()))
+        }
+        other => Err(EvalError::TypeError(format!(
+            "'{}' object is not sliceable",
+            other.type_name()
+        ))),
+    }
+}
+
+fn to_index(value: &Value) -> Result<i64, EvalError> {
+    match value {
+        Value::Int(i) => i64::try_from(i).map_err(|_| EvalError::TypeError("index out of range".to_string())),
+        other => Err(EvalError::TypeError(format!(
+            "indices must be integers, not '{}'",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Resolves a single `a[i]` index (negative counts from the end) to a
+/// bounds-checked position, or an out-of-range error.
+fn normalize_index(i: i64, len: usize) -> Result<usize, EvalError> {
+    let len = len as i64;
+    let adjusted = if i < 0 { i + len } else { i };
+    if adjusted < 0 || adjusted >= len {
+        Err(EvalError::TypeError("index out of range".to_string()))
+    } else {
+        Ok(adjusted as usize)
+    }
+}
+
+/// Clamps a possibly-negative, possibly-out-of-bounds slice endpoint
+/// into `[lower, upper]`, the way CPython's `slice.indices` does.
+fn clamp_slice_endpoint(i: i64, len: i64, lower: i64, upper: i64) -> i64 {
+    let i = if i < 0 { i + len } else { i };
+    i.clamp(lower, upper)
+}
+
+/// Resolves `start`/`stop`/`step` into the sequence of positions a
+/// slice selects, defaulting a missing `start`/`stop` by the sign of
+/// `step` the way Starlark does.
+fn slice_indices(len: usize, start: Option<i64>, stop: Option<i64>, step: i64) -> Vec<usize> {
+    let len = len as i64;
+    let (lower, upper, default_start, default_stop) =
+        if step > 0 { (0, len, 0, len) } else { (-1, len - 1, len - 1, -1) };
+    let start = start.map(|s| clamp_slice_endpoint(s, len, lower, upper)).unwrap_or(default_start);
+    let stop = stop.map(|s| clamp_slice_endpoint(s, len, lower, upper)).unwrap_or(default_stop);
+
+    let mut indices = Vec::new();
+    let mut i = start;
+    if step > 0 {
+        while i < stop {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else {
+        while i > stop {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
+
+fn add(lhs: Value, rhs: Value) -> Result<Value, EvalError> {
+    match (lhs, rhs) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(&a + &b)),
+        (Value::String(mut a), Value::String(b)) => {
+            a.push_str(&b);
+            Ok(Value::String(a))
+        }
+        (Value::Bytes(mut a), Value::Bytes(b)) => {
+            a.extend(b);
+            Ok(Value::Bytes(a))
+        }
+        (Value::List(mut a), Value::List(b)) => {
+            a.extend(b);
+            Ok(Value::List(a))
+        }
+        (Value::Tuple(mut a), Value::Tuple(b)) => {
+            a.extend(b);
+            Ok(Value::Tuple(a))
+        }
+        (lhs, rhs) => Err(EvalError::TypeError(format!(
+            "unsupported operand type(s) for +: '{}' and '{}'",
+            lhs.type_name(),
+            rhs.type_name()
+        ))),
+    }
+}
+
+fn int_op(
+    lhs: &Expr,
+    rhs: &Expr,
+    env: &mut Env,
+    op: &str,
+    f: impl FnOnce(BigInt, BigInt) -> BigInt,
+) -> Result<Value, EvalError> {
+    match (eval(lhs, env)?, eval(rhs, env)?) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(f(a, b))),
+        (lhs, rhs) => Err(EvalError::TypeError(format!(
+            "unsupported operand type(s) for {}: '{}' and '{}'",
+            op,
+            lhs.type_name(),
+            rhs.type_name()
+        ))),
+    }
+}
+
+/// Like `int_op`, but for `/`, `//`, and `%`, which must reject a zero
+/// right-hand side before `f` ever runs.
+fn checked_int_op(
+    lhs: &Expr,
+    rhs: &Expr,
+    env: &mut Env,
+    op: &str,
+    f: impl FnOnce(BigInt, BigInt) -> BigInt,
+) -> Result<Value, EvalError> {
+    match (eval(lhs, env)?, eval(rhs, env)?) {
+        (Value::Int(_), Value::Int(b)) if b.sign() == Sign::NoSign => Err(EvalError::DivisionByZero),
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(f(a, b))),
+        (lhs, rhs) => Err(EvalError::TypeError(format!(
+            "unsupported operand type(s) for {}: '{}' and '{}'",
+            op,
+            lhs.type_name(),
+            rhs.type_name()
+        ))),
+    }
+}
+
+/// Starlark's `//` rounds toward negative infinity rather than zero.
+fn div_floor(a: BigInt, b: BigInt) -> BigInt {
+    let r = &a % &b;
+    let q = &a / &b;
+    if r.sign() != Sign::NoSign && (r.sign() == Sign::Minus) != (b.sign() == Sign::Minus) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// Starlark's `%` follows `//`: the result has the same sign as the
+/// divisor, matching Python's modulo rather than Rust's truncating one.
+fn mod_floor(a: BigInt, b: BigInt) -> BigInt {
+    let r = &a % &b;
+    if r.sign() != Sign::NoSign && (r.sign() == Sign::Minus) != (b.sign() == Sign::Minus) {
+        r + b
+    } else {
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Spanned;
+
+    // Span bounds don't matter to the evaluator, so tests use (0, 0) throughout.
+    fn sp(node: Expr) -> Box<Spanned<Expr>> {
+        Box::new(Spanned::new(node, 0, 0))
+    }
+
+    fn int(n: i64) -> Expr {
+        Expr::Int(BigInt::from(n))
+    }
+
+    fn val(n: i64) -> Value {
+        Value::Int(BigInt::from(n))
+    }
+
+    fn eval_expr(expr: Expr) -> Result<Value, EvalError> {
+        eval(&expr, &mut Env::new())
+    }
+
+    #[test]
+    fn arithmetic() {
+        let expr = Expr::Add(sp(int(1)), sp(Expr::Mul(sp(int(2)), sp(int(3)))));
+        assert_eq!(eval_expr(expr), Ok(val(7)));
+    }
+
+    #[test]
+    fn floor_division() {
+        let expr = Expr::DivFloor(sp(int(-7)), sp(int(2)));
+        assert_eq!(eval_expr(expr), Ok(val(-4)));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let expr = Expr::Div(sp(int(1)), sp(int(0)));
+        assert_eq!(eval_expr(expr), Err(EvalError::DivisionByZero));
+    }
+
+    #[test]
+    fn string_concatenation() {
+        let expr = Expr::Add(sp(Expr::String("foo".to_string())), sp(Expr::String("bar".to_string())));
+        assert_eq!(eval_expr(expr), Ok(Value::String("foobar".to_string())));
+    }
+
+    #[test]
+    fn bytes_concatenation() {
+        let expr = Expr::Add(sp(Expr::Bytes(b"foo".to_vec())), sp(Expr::Bytes(b"bar".to_vec())));
+        assert_eq!(eval_expr(expr), Ok(Value::Bytes(b"foobar".to_vec())));
+    }
+
+    #[test]
+    fn short_circuit_or() {
+        // `1 or (1 // 0)` must not evaluate the right-hand side.
+        let expr = Expr::Or(sp(int(1)), sp(Expr::DivFloor(sp(int(1)), sp(int(0)))));
+        assert_eq!(eval_expr(expr), Ok(val(1)));
+    }
+
+    #[test]
+    fn truthiness() {
+        assert!(!Value::None.is_truthy());
+        assert!(!val(0).is_truthy());
+        assert!(!Value::List(vec![]).is_truthy());
+        assert!(val(1).is_truthy());
+    }
+
+    #[test]
+    fn identifier_lookup() {
+        let mut env = Env::new();
+        env.set("x", val(42));
+        assert_eq!(eval(&Expr::Identifier("x".to_string()), &mut env), Ok(val(42)));
+        assert_eq!(
+            eval(&Expr::Identifier("y".to_string()), &mut env),
+            Err(EvalError::NameError("y".to_string()))
+        );
+    }
+
+    #[test]
+    fn index_list_and_negative_index() {
+        let list = Expr::List(vec![*sp(int(1)), *sp(int(2)), *sp(int(3))]);
+        let expr = Expr::Slice(sp(list.clone()), Box::new(crate::ast::Slice {
+            index: SliceIndex::Index(sp(int(-1))),
+        }));
+        assert_eq!(eval_expr(expr), Ok(val(3)));
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        let list = Expr::List(vec![*sp(int(1))]);
+        let expr = Expr::Slice(sp(list), Box::new(crate::ast::Slice {
+            index: SliceIndex::Index(sp(int(5))),
+        }));
+        assert!(eval_expr(expr).is_err());
+    }
+
+    #[test]
+    fn slice_range_with_step() {
+        let list = Expr::List((0..5).map(|n| *sp(int(n))).collect());
+        let expr = Expr::Slice(sp(list), Box::new(crate::ast::Slice {
+            index: SliceIndex::Range { start: None, stop: None, step: Some(sp(int(2))) },
+        }));
+        assert_eq!(eval_expr(expr), Ok(Value::List(vec![val(0), val(2), val(4)])));
+    }
+
+    #[test]
+    fn dot_and_call_are_unsupported() {
+        let expr = Expr::Dot(sp(int(1)), "foo".to_string());
+        assert!(matches!(eval_expr(expr), Err(EvalError::Unsupported("attribute access"))));
+
+        let expr = Expr::Call(sp(int(1)), Box::new(crate::ast::Call { args: vec![] }));
+        assert!(matches!(eval_expr(expr), Err(EvalError::Unsupported("calls"))));
+    }
+
+    #[test]
+    fn list_comprehension() {
+        // [n * n for n in [1, 2, 3]]
+        let expr = Expr::ListComp {
+            element: sp(Expr::Mul(sp(Expr::Identifier("n".to_string())), sp(Expr::Identifier("n".to_string())))),
+            clauses: vec![CompClause::For {
+                targets: vec![*sp(Expr::Identifier("n".to_string()))],
+                iter: sp(Expr::List(vec![*sp(int(1)), *sp(int(2)), *sp(int(3))])),
+            }],
+        };
+        assert_eq!(eval_expr(expr), Ok(Value::List(vec![val(1), val(4), val(9)])));
+    }
+
+    #[test]
+    fn list_comprehension_with_if_clause() {
+        // [n for n in [1, 2, 3] if n > 1]
+        let expr = Expr::ListComp {
+            element: sp(Expr::Identifier("n".to_string())),
+            clauses: vec![
+                CompClause::For {
+                    targets: vec![*sp(Expr::Identifier("n".to_string()))],
+                    iter: sp(Expr::List(vec![*sp(int(1)), *sp(int(2)), *sp(int(3))])),
+                },
+                CompClause::If(sp(Expr::Gt(sp(Expr::Identifier("n".to_string())), sp(int(1))))),
+            ],
+        };
+        assert_eq!(eval_expr(expr), Ok(Value::List(vec![val(2), val(3)])));
+    }
+
+    #[test]
+    fn dict_comprehension() {
+        // {n: n * n for n in [1, 2]}
+        let expr = Expr::DictComp {
+            key: sp(Expr::Identifier("n".to_string())),
+            value: sp(Expr::Mul(sp(Expr::Identifier("n".to_string())), sp(Expr::Identifier("n".to_string())))),
+            clauses: vec![CompClause::For {
+                targets: vec![*sp(Expr::Identifier("n".to_string()))],
+                iter: sp(Expr::List(vec![*sp(int(1)), *sp(int(2))])),
+            }],
+        };
+        assert_eq!(eval_expr(expr), Ok(Value::Dict(vec![(val(1), val(1)), (val(2), val(4))])));
+    }
+
+    #[test]
+    fn dict_display() {
+        let expr = Expr::Dict(vec![(*sp(Expr::String("a".to_string())), *sp(int(1)))]);
+        assert_eq!(eval_expr(expr), Ok(Value::Dict(vec![(Value::String("a".to_string()), val(1))])));
+    }
+
+    #[test]
+    fn unpacking_count_mismatch_errors() {
+        // for a, b in [(1, 2, 3)]: a
+        let expr = Expr::ListComp {
+            element: sp(Expr::Identifier("a".to_string())),
+            clauses: vec![CompClause::For {
+                targets: vec![
+                    *sp(Expr::Identifier("a".to_string())),
+                    *sp(Expr::Identifier("b".to_string())),
+                ],
+                iter: sp(Expr::List(vec![*sp(Expr::Tuple(vec![
+                    *sp(int(1)),
+                    *sp(int(2)),
+                    *sp(int(3)),
+                ]))])),
+            }],
+        };
+        assert!(matches!(eval_expr(expr), Err(EvalError::TypeError(_))));
+    }
+
+    #[test]
+    fn scoped_lookup() {
+        let mut env = Env::new();
+        env.set("x", val(1));
+        env.push_scope();
+        env.set("x", val(2));
+        assert_eq!(env.get("x"), Some(&val(2)));
+        env.pop_scope();
+        assert_eq!(env.get("x"), Some(&val(1)));
+    }
+}
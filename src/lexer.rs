@@ -0,0 +1,702 @@
+//! A hand-written lexer that lalrpop drives via `parse(Lexer::new(src))`.
+//!
+//! Tokens are `(usize, Tok, usize)` triples of byte offsets, the shape
+//! lalrpop expects from an external lexer. Starlark's grammar is
+//! indentation-sensitive, so this is also where `NEWLINE`/`INDENT`/
+//! `DEDENT` get synthesized from a column stack, the same way Python's
+//! tokenizer does it.
+
+use std::collections::VecDeque;
+use std::str::CharIndices;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tok<'input> {
+    Identifier(&'input str),
+    Int(&'input str),
+    Str(String),
+    Bytes(Vec<u8>),
+
+    And,
+    Or,
+    Not,
+    In,
+    If,
+    Elif,
+    Else,
+    For,
+    Def,
+    Return,
+    Break,
+    Continue,
+    Pass,
+    Load,
+    True,
+    False,
+    None_,
+
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    Comma,
+    Colon,
+    Dot,
+    Semi,
+
+    Assign,
+    PlusEq,
+    MinusEq,
+    StarEq,
+    SlashEq,
+    SlashSlashEq,
+    PercentEq,
+
+    Plus,
+    Minus,
+    Star,
+    StarStar,
+    Slash,
+    SlashSlash,
+    Percent,
+    Pipe,
+    Amp,
+
+    EqEq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+
+    Newline,
+    Indent,
+    Dedent,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexError {
+    UnexpectedChar(usize, char),
+    InconsistentDedent(usize),
+    InvalidIntLiteral(usize),
+    UnexpectedEof,
+}
+
+/// A `ParseError` carries a message and the 1-based line/column the
+/// original byte offset maps to, for user-facing diagnostics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Converts a raw byte offset into a 1-based `(line, column)` pair.
+    pub fn line_col(src: &str, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in src.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    pub fn from_lex_error(src: &str, err: LexError) -> Self {
+        let (offset, message) = match err {
+            LexError::UnexpectedChar(offset, ch) => (offset, format!("unexpected character '{}'", ch)),
+            LexError::InconsistentDedent(offset) => {
+                (offset, "dedent does not match any outer indentation level".to_string())
+            }
+            LexError::InvalidIntLiteral(offset) => (offset, "invalid integer literal".to_string()),
+            LexError::UnexpectedEof => (src.len(), "unexpected end of file".to_string()),
+        };
+        let (line, column) = Self::line_col(src, offset);
+        ParseError { message, line, column }
+    }
+}
+
+fn keyword(word: &str) -> Option<Tok<'static>> {
+    Some(match word {
+        "and" => Tok::And,
+        "or" => Tok::Or,
+        "not" => Tok::Not,
+        "in" => Tok::In,
+        "if" => Tok::If,
+        "elif" => Tok::Elif,
+        "else" => Tok::Else,
+        "for" => Tok::For,
+        "def" => Tok::Def,
+        "return" => Tok::Return,
+        "break" => Tok::Break,
+        "continue" => Tok::Continue,
+        "pass" => Tok::Pass,
+        "load" => Tok::Load,
+        "True" => Tok::True,
+        "False" => Tok::False,
+        "None" => Tok::None_,
+        _ => return None,
+    })
+}
+
+/// Appends a decoded character to whichever buffer the literal is
+/// building: UTF-8 bytes for a byte string, the char itself for text.
+fn push_char(text: &mut String, data: &mut Vec<u8>, bytes: bool, c: char) {
+    if bytes {
+        let mut buf = [0u8; 4];
+        data.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    } else {
+        text.push(c);
+    }
+}
+
+type Spanned<'input> = (usize, Tok<'input>, usize);
+
+pub struct Lexer<'input> {
+    input: &'input str,
+    chars: std::iter::Peekable<CharIndices<'input>>,
+    pending: VecDeque<Spanned<'input>>,
+    indents: Vec<usize>,
+    paren_depth: i32,
+    at_line_start: bool,
+    done: bool,
+}
+
+impl<'input> Lexer<'input> {
+    pub fn new(input: &'input str) -> Self {
+        Lexer {
+            input,
+            chars: input.char_indices().peekable(),
+            pending: VecDeque::new(),
+            indents: vec![0],
+            paren_depth: 0,
+            at_line_start: true,
+            done: false,
+        }
+    }
+
+    fn bump(&mut self) -> Option<(usize, char)> {
+        self.chars.next()
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    /// Consumes leading whitespace on a logical line and, once a
+    /// non-blank line is found, reconciles the new indentation against
+    /// `self.indents`, queuing `Indent`/`Dedent` tokens as needed.
+    /// Returns `Ok(false)` for blank/comment-only lines so the caller
+    /// keeps scanning.
+    fn handle_line_start(&mut self) -> Result<bool, LexError> {
+        let line_start = self.chars.peek().map(|&(i, _)| i).unwrap_or(self.input.len());
+        let mut col = 0;
+        loop {
+            match self.peek_char() {
+                Some(' ') => {
+                    col += 1;
+                    self.bump();
+                }
+                Some('\t') => {
+                    col += 8 - (col % 8);
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+        match self.peek_char() {
+            None => {
+                // EOF: dedent all the way back to column 0.
+                while self.indents.len() > 1 {
+                    self.indents.pop();
+                    self.pending.push_back((line_start, Tok::Dedent, line_start));
+                }
+                return Ok(true);
+            }
+            Some('#') => {
+                while !matches!(self.peek_char(), Some('\n') | None) {
+                    self.bump();
+                }
+                return Ok(false);
+            }
+            Some('\n') => {
+                self.bump();
+                return Ok(false);
+            }
+            _ => {}
+        }
+
+        let current = *self.indents.last().unwrap();
+        if col > current {
+            self.indents.push(col);
+            self.pending.push_back((line_start, Tok::Indent, line_start));
+        } else if col < current {
+            while *self.indents.last().unwrap() > col {
+                self.indents.pop();
+                self.pending.push_back((line_start, Tok::Dedent, line_start));
+            }
+            if *self.indents.last().unwrap() != col {
+                return Err(LexError::InconsistentDedent(line_start));
+            }
+        }
+        Ok(true)
+    }
+
+    fn lex_identifier_or_keyword(&mut self, start: usize) -> Spanned<'input> {
+        let mut end = start + 1;
+        while let Some(c) = self.peek_char() {
+            if c.is_alphanumeric() || c == '_' {
+                end += c.len_utf8();
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        let text = &self.input[start..end];
+        let tok = keyword(text).unwrap_or(Tok::Identifier(text));
+        (start, tok, end)
+    }
+
+    /// Scans a run of alphanumerics and `_` digit separators; radix
+    /// prefixes (`0x`/`0o`/`0b`) and separator stripping happen later in
+    /// `parse_int`, not here.
+    fn lex_number(&mut self, start: usize) -> Spanned<'input> {
+        let mut end = start + 1;
+        while let Some(c) = self.peek_char() {
+            if c.is_alphanumeric() || c == '_' {
+                end += c.len_utf8();
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        (start, Tok::Int(&self.input[start..end]), end)
+    }
+
+    /// Looks ahead (without consuming) to see whether the prefix letter
+    /// just consumed at `start` (`r`/`R`/`b`/`B`) actually introduces a
+    /// string literal, i.e. is immediately followed by a quote, or by
+    /// the other prefix letter and then a quote (`rb"..."`/`br"..."`).
+    /// Returns `(is_raw, is_bytes)` and consumes the second prefix
+    /// letter if there is one; returns `None` and consumes nothing if
+    /// this isn't a string prefix after all (so it falls back to being
+    /// lexed as a plain identifier).
+    fn lex_string_prefix(&mut self, start: usize) -> Option<(bool, bool)> {
+        let first = self.input[start..].chars().next().unwrap().to_ascii_lowercase();
+        let mut raw = first == 'r';
+        let mut bytes = first == 'b';
+
+        let mut lookahead = self.chars.clone();
+        match lookahead.next() {
+            Some((_, c)) if c == '"' || c == '\'' => Some((raw, bytes)),
+            Some((_, c)) => {
+                let lower = c.to_ascii_lowercase();
+                let is_other_prefix = (lower == 'r' && !raw) || (lower == 'b' && !bytes);
+                if is_other_prefix && matches!(lookahead.next(), Some((_, q)) if q == '"' || q == '\'') {
+                    self.bump();
+                    raw = true;
+                    bytes = true;
+                    Some((raw, bytes))
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    }
+
+    /// Lexes the body of a string/bytes literal. `quote` is the opening
+    /// quote character, already consumed by the caller; this detects a
+    /// triple-quoted literal by peeking for two more of the same quote.
+    fn lex_string_body(
+        &mut self,
+        start: usize,
+        quote: char,
+        raw: bool,
+        bytes: bool,
+    ) -> Result<Spanned<'input>, LexError> {
+        let triple = {
+            let mut lookahead = self.chars.clone();
+            matches!(lookahead.next(), Some((_, c)) if c == quote)
+                && matches!(lookahead.next(), Some((_, c)) if c == quote)
+        };
+        if triple {
+            self.bump();
+            self.bump();
+        }
+
+        let mut text = String::new();
+        let mut data = Vec::new();
+        loop {
+            let (pos, c) = self.bump().ok_or(LexError::UnexpectedEof)?;
+            if c == quote {
+                if !triple {
+                    break;
+                }
+                let mut lookahead = self.chars.clone();
+                let closes = matches!(lookahead.next(), Some((_, c)) if c == quote)
+                    && matches!(lookahead.next(), Some((_, c)) if c == quote);
+                if closes {
+                    self.bump();
+                    self.bump();
+                    break;
+                }
+                push_char(&mut text, &mut data, bytes, c);
+                continue;
+            }
+            if !triple && c == '\n' {
+                return Err(LexError::UnexpectedChar(pos, c));
+            }
+            if c == '\\' {
+                if raw {
+                    push_char(&mut text, &mut data, bytes, c);
+                    if self.peek_char() == Some(quote) {
+                        self.bump();
+                        push_char(&mut text, &mut data, bytes, quote);
+                    }
+                } else {
+                    self.lex_escape(pos, &mut text, &mut data, bytes)?;
+                }
+                continue;
+            }
+            push_char(&mut text, &mut data, bytes, c);
+        }
+
+        let end = self.chars.peek().map(|&(i, _)| i).unwrap_or(self.input.len());
+        let tok = if bytes { Tok::Bytes(data) } else { Tok::Str(text) };
+        Ok((start, tok, end))
+    }
+
+    /// Decodes the character(s) following a `\` inside a non-raw string.
+    ///
+    /// `\xHH` is valid in both string and byte-string literals: a raw
+    /// byte in a byte string, code point U+00HH in a string;
+    /// `\uXXXX`/`\UXXXXXXXX` name a Unicode code point, so only
+    /// string literals accept them — a byte string has no such thing as
+    /// "the UTF-8 encoding of this escape" to fall back on.
+    fn lex_escape(
+        &mut self,
+        backslash_pos: usize,
+        text: &mut String,
+        data: &mut Vec<u8>,
+        bytes: bool,
+    ) -> Result<(), LexError> {
+        let (_, c) = self.bump().ok_or(LexError::UnexpectedEof)?;
+        let code_point = match c {
+            'n' => '\n' as u32,
+            't' => '\t' as u32,
+            'r' => '\r' as u32,
+            '\\' => '\\' as u32,
+            '\'' => '\'' as u32,
+            '"' => '"' as u32,
+            '\n' => return Ok(()), // backslash-newline is a line continuation
+            '0'..='7' => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek_char().and_then(|d| d.to_digit(8)) {
+                        Some(d) => {
+                            value = value * 8 + d;
+                            self.bump();
+                        }
+                        None => break,
+                    }
+                }
+                value
+            }
+            'x' => {
+                let value = self.read_hex_digits(backslash_pos, 2)?;
+                if bytes {
+                    data.push(value as u8);
+                    return Ok(());
+                }
+                value
+            }
+            'u' | 'U' if bytes => return Err(LexError::UnexpectedChar(backslash_pos, c)),
+            'u' => self.read_hex_digits(backslash_pos, 4)?,
+            'U' => self.read_hex_digits(backslash_pos, 8)?,
+            other => return Err(LexError::UnexpectedChar(backslash_pos, other)),
+        };
+        if bytes {
+            // Reached from the single-character escapes and the octal arm;
+            // an octal escape above `\377` doesn't fit in a byte and is
+            // rejected rather than truncated.
+            let byte = u8::try_from(code_point).map_err(|_| LexError::UnexpectedChar(backslash_pos, c))?;
+            data.push(byte);
+        } else {
+            let ch = char::from_u32(code_point).ok_or(LexError::UnexpectedChar(backslash_pos, c))?;
+            text.push(ch);
+        }
+        Ok(())
+    }
+
+    fn read_hex_digits(&mut self, pos: usize, count: usize) -> Result<u32, LexError> {
+        let mut value = 0u32;
+        for _ in 0..count {
+            let digit = self
+                .peek_char()
+                .and_then(|c| c.to_digit(16))
+                .ok_or(LexError::UnexpectedChar(pos, 'x'))?;
+            value = value * 16 + digit;
+            self.bump();
+        }
+        Ok(value)
+    }
+
+    /// Matches the character just consumed at `start`, followed
+    /// optionally by `second`, against a two character operator (`two`),
+    /// falling back to `one` when it's just the first character.
+    fn two_char(
+        &mut self,
+        start: usize,
+        second: char,
+        two: Tok<'input>,
+        one: Tok<'input>,
+    ) -> Spanned<'input> {
+        if self.peek_char() == Some(second) {
+            self.bump();
+            (start, two, start + 2)
+        } else {
+            (start, one, start + 1)
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<Spanned<'input>, LexError>> {
+        loop {
+            if self.at_line_start && self.paren_depth == 0 {
+                match self.handle_line_start() {
+                    Ok(true) => self.at_line_start = false,
+                    Ok(false) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            if let Some(tok) = self.pending.pop_front() {
+                return Some(Ok(tok));
+            }
+
+            let (start, ch) = match self.bump() {
+                Some(pair) => pair,
+                None => {
+                    if self.done {
+                        return None;
+                    }
+                    self.done = true;
+                    while self.indents.len() > 1 {
+                        self.indents.pop();
+                        self.pending.push_back((self.input.len(), Tok::Dedent, self.input.len()));
+                    }
+                    return self.pending.pop_front().map(Ok);
+                }
+            };
+
+            let tok = match ch {
+                ' ' | '\t' => continue,
+                '\\' if self.peek_char() == Some('\n') => {
+                    self.bump();
+                    continue;
+                }
+                '#' => {
+                    while !matches!(self.peek_char(), Some('\n') | None) {
+                        self.bump();
+                    }
+                    continue;
+                }
+                '\n' => {
+                    self.at_line_start = true;
+                    if self.paren_depth > 0 {
+                        continue;
+                    }
+                    (start, Tok::Newline, start + 1)
+                }
+                '(' => {
+                    self.paren_depth += 1;
+                    (start, Tok::LParen, start + 1)
+                }
+                ')' => {
+                    self.paren_depth -= 1;
+                    (start, Tok::RParen, start + 1)
+                }
+                '[' => {
+                    self.paren_depth += 1;
+                    (start, Tok::LBracket, start + 1)
+                }
+                ']' => {
+                    self.paren_depth -= 1;
+                    (start, Tok::RBracket, start + 1)
+                }
+                '{' => {
+                    self.paren_depth += 1;
+                    (start, Tok::LBrace, start + 1)
+                }
+                '}' => {
+                    self.paren_depth -= 1;
+                    (start, Tok::RBrace, start + 1)
+                }
+                ',' => (start, Tok::Comma, start + 1),
+                ':' => (start, Tok::Colon, start + 1),
+                '.' => (start, Tok::Dot, start + 1),
+                ';' => (start, Tok::Semi, start + 1),
+                '|' => (start, Tok::Pipe, start + 1),
+                '&' => (start, Tok::Amp, start + 1),
+                '+' => self.two_char(start, '=', Tok::PlusEq, Tok::Plus),
+                '-' => self.two_char(start, '=', Tok::MinusEq, Tok::Minus),
+                '*' => {
+                    if self.peek_char() == Some('*') {
+                        self.bump();
+                        (start, Tok::StarStar, start + 2)
+                    } else {
+                        self.two_char(start, '=', Tok::StarEq, Tok::Star)
+                    }
+                }
+                '%' => self.two_char(start, '=', Tok::PercentEq, Tok::Percent),
+                '=' => self.two_char(start, '=', Tok::EqEq, Tok::Assign),
+                '<' => self.two_char(start, '=', Tok::Le, Tok::Lt),
+                '>' => self.two_char(start, '=', Tok::Ge, Tok::Gt),
+                '!' => {
+                    if self.peek_char() == Some('=') {
+                        self.bump();
+                        (start, Tok::Ne, start + 2)
+                    } else {
+                        return Some(Err(LexError::UnexpectedChar(start, ch)));
+                    }
+                }
+                '/' => {
+                    if self.peek_char() == Some('/') {
+                        self.bump();
+                        if self.peek_char() == Some('=') {
+                            self.bump();
+                            (start, Tok::SlashSlashEq, start + 3)
+                        } else {
+                            (start, Tok::SlashSlash, start + 2)
+                        }
+                    } else {
+                        self.two_char(start, '=', Tok::SlashEq, Tok::Slash)
+                    }
+                }
+                '"' | '\'' => return Some(self.lex_string_body(start, ch, false, false)),
+                'r' | 'R' | 'b' | 'B' => match self.lex_string_prefix(start) {
+                    Some((raw, bytes)) => match self.bump() {
+                        Some((_, quote)) if quote == '"' || quote == '\'' => {
+                            return Some(self.lex_string_body(start, quote, raw, bytes))
+                        }
+                        _ => return Some(Err(LexError::UnexpectedEof)),
+                    },
+                    None => self.lex_identifier_or_keyword(start),
+                },
+                c if c.is_ascii_digit() => self.lex_number(start),
+                c if c.is_alphabetic() || c == '_' => self.lex_identifier_or_keyword(start),
+                c => return Some(Err(LexError::UnexpectedChar(start, c))),
+            };
+            return Some(Ok(tok));
+        }
+    }
+}
+
+impl<'input> Iterator for Lexer<'input> {
+    type Item = Result<Spanned<'input>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(tok) = self.pending.pop_front() {
+            return Some(Ok(tok));
+        }
+        self.next_token()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toks(src: &str) -> Vec<Tok<'_>> {
+        Lexer::new(src).map(|r| r.unwrap().1).collect()
+    }
+
+    #[test]
+    fn identifiers_and_keywords() {
+        assert_eq!(
+            toks("foo and not bar\n"),
+            vec![Tok::Identifier("foo"), Tok::And, Tok::Not, Tok::Identifier("bar"), Tok::Newline]
+        );
+    }
+
+    #[test]
+    fn indent_and_dedent() {
+        let src = "if x:\n    pass\ny\n";
+        assert_eq!(
+            toks(src),
+            vec![
+                Tok::If,
+                Tok::Identifier("x"),
+                Tok::Colon,
+                Tok::Newline,
+                Tok::Indent,
+                Tok::Pass,
+                Tok::Newline,
+                Tok::Dedent,
+                Tok::Identifier("y"),
+                Tok::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn inconsistent_dedent_errors() {
+        let src = "if x:\n    pass\n  y\n";
+        let result: Result<Vec<_>, _> = Lexer::new(src).collect();
+        assert!(matches!(result, Err(LexError::InconsistentDedent(_))));
+    }
+
+    #[test]
+    fn string_escapes() {
+        let src = "\"a\\tb\\x41\\u0042\"\n";
+        assert_eq!(toks(src), vec![Tok::Str("a\tbAB".to_string()), Tok::Newline]);
+    }
+
+    #[test]
+    fn byte_string_hex_escape_is_a_single_byte() {
+        let src = "b\"\\xff\"\n";
+        assert_eq!(toks(src), vec![Tok::Bytes(vec![0xff]), Tok::Newline]);
+    }
+
+    #[test]
+    fn byte_string_rejects_unicode_escapes() {
+        let src = "b\"\\u0041\"\n";
+        let result: Result<Vec<_>, _> = Lexer::new(src).collect();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn raw_string_keeps_backslashes() {
+        let src = "r\"a\\nb\"\n";
+        assert_eq!(toks(src), vec![Tok::Str("a\\nb".to_string()), Tok::Newline]);
+    }
+
+    #[test]
+    fn triple_quoted_string_allows_newlines() {
+        let src = "\"\"\"a\nb\"\"\"\n";
+        assert_eq!(toks(src), vec![Tok::Str("a\nb".to_string()), Tok::Newline]);
+    }
+
+    #[test]
+    fn unterminated_string_errors() {
+        let result: Result<Vec<_>, _> = Lexer::new("\"abc").collect();
+        assert_eq!(result, Err(LexError::UnexpectedEof));
+    }
+}
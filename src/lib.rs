@@ -1,43 +1,210 @@
 #[macro_use]
 extern crate lalrpop_util;
 
-lalrpop_mod!(skylark);
+lalrpop_mod!(#[allow(clippy::all)] skylark);
 
 mod ast;
+mod grammar_support;
+mod interpreter;
+mod lexer;
+
+use ast::SStatement;
+use lexer::{Lexer, ParseError};
+
+#[cfg(test)]
+use num_bigint::BigInt;
+
+/// Parses a full Starlark source file into its top-level statements.
+pub fn parse_module(src: &str) -> Result<Vec<SStatement>, ParseError> {
+    skylark::ModuleParser::new().parse(Lexer::new(src)).map_err(|e| match e {
+        lalrpop_util::ParseError::User { error } => ParseError::from_lex_error(src, error),
+        other => {
+            let (offset, message) = match &other {
+                lalrpop_util::ParseError::InvalidToken { location } => {
+                    (*location, "invalid token".to_string())
+                }
+                lalrpop_util::ParseError::UnrecognizedEof { location, expected } => {
+                    (*location, format!("unexpected end of file, expected one of: {}", expected.join(", ")))
+                }
+                lalrpop_util::ParseError::UnrecognizedToken { token: (start, tok, _), expected } => {
+                    (*start, format!("unexpected token {:?}, expected one of: {}", tok, expected.join(", ")))
+                }
+                lalrpop_util::ParseError::ExtraToken { token: (start, tok, _) } => {
+                    (*start, format!("unexpected extra token {:?}", tok))
+                }
+                lalrpop_util::ParseError::User { .. } => unreachable!(),
+            };
+            let (line, column) = ParseError::line_col(src, offset);
+            ParseError { message, line, column }
+        }
+    })
+}
 
 #[test]
 fn zero() {
-    assert_eq!(skylark::IntParser::new().parse("0"), Ok(0));
-    assert_eq!(skylark::IntParser::new().parse("00000"), Ok(0));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0")), Ok(BigInt::from(0)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("00000")), Ok(BigInt::from(0)));
 }
 
 #[test]
 fn decimal() {
-    assert_eq!(skylark::IntParser::new().parse("8"), Ok(8));
-    assert_eq!(skylark::IntParser::new().parse("10"), Ok(10));
-    assert!(skylark::IntParser::new().parse("01").is_err());
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("8")), Ok(BigInt::from(8)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("10")), Ok(BigInt::from(10)));
+    assert!(skylark::IntParser::new().parse(Lexer::new("01")).is_err());
 }
 
 #[test]
 fn octal() {
-    assert_eq!(skylark::IntParser::new().parse("0o7"), Ok(7));
-    assert_eq!(skylark::IntParser::new().parse("0O7"), Ok(7));
-    assert_eq!(skylark::IntParser::new().parse("0O777"), Ok(0o777));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0o7")), Ok(BigInt::from(7)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0O7")), Ok(BigInt::from(7)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0O777")), Ok(BigInt::from(0o777)));
 }
 
 #[test]
 fn hexadecimal() {
-    assert_eq!(skylark::IntParser::new().parse("0x7"), Ok(7));
-    assert_eq!(skylark::IntParser::new().parse("0X7"), Ok(7));
-    assert_eq!(skylark::IntParser::new().parse("0xffe"), Ok(0xffe));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0x7")), Ok(BigInt::from(7)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0X7")), Ok(BigInt::from(7)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0xffe")), Ok(BigInt::from(0xffe)));
+}
+
+#[test]
+fn binary_and_underscore_separators() {
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("0b1010")), Ok(BigInt::from(0b1010)));
+    assert_eq!(skylark::IntParser::new().parse(Lexer::new("1_000_000")), Ok(BigInt::from(1_000_000)));
+}
+
+#[test]
+fn beyond_i64() {
+    use std::str::FromStr;
+
+    let huge = "999999999999999999999999999999999999999999";
+    assert_eq!(
+        skylark::IntParser::new().parse(Lexer::new(huge)),
+        Ok(BigInt::from_str(huge).unwrap())
+    );
 }
 
 #[test]
 fn identifier() {
-    assert!(skylark::IdentifierParser::new().parse("0x7").is_err());
-    assert!(skylark::IdentifierParser::new().parse("foo").is_ok());
-    assert!(skylark::IdentifierParser::new().parse("_foo").is_ok());
-    assert!(skylark::IdentifierParser::new().parse("__foo").is_ok());
-    assert!(skylark::IdentifierParser::new().parse("Foo").is_ok());
-    assert!(skylark::IdentifierParser::new().parse("F0ooBar").is_ok());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("0x7")).is_err());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("foo")).is_ok());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("_foo")).is_ok());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("__foo")).is_ok());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("Foo")).is_ok());
+    assert!(skylark::IdentifierParser::new().parse(Lexer::new("F0ooBar")).is_ok());
+}
+
+#[test]
+fn module_statements() {
+    let src = "\
+def greet(name, *args, **kwargs):
+    if name == \"\":
+        return None
+    else:
+        greeting = \"hi\" + name
+        return greeting
+
+for x in [1, 2, 3]:
+    total = total + x
+
+load(\"//lib:util.bzl\", \"helper\", h2=\"helper2\")
+";
+    let module = skylark::ModuleParser::new().parse(Lexer::new(src));
+    assert!(module.is_ok(), "{:?}", module);
+    assert_eq!(module.unwrap().len(), 3);
+}
+
+/// Parses a small module exercising most AST node kinds, serializes it
+/// to JSON, and compares against a checked-in fixture — a regression
+/// test for the `serde` representation itself (field names, enum
+/// tagging, etc.), not just whether (de)serializing compiles.
+#[test]
+#[cfg(feature = "serde")]
+fn serde_roundtrip_matches_golden_fixture() {
+    let src = "\
+def greet(name, *args, **kwargs):
+    if name == \"\":
+        return None
+    greeting = \"hi\" + name
+    return greeting[1:]
+
+numbers = [n * n for n in [1, 2, 3] if n > 1]
+point = {\"x\": 1, \"y\": 2}
+total = point[\"x\"] + numbers[0]
+";
+    let module = parse_module(src).expect("fixture source should parse");
+    let actual = serde_json::to_string_pretty(&module).unwrap();
+    let expected = include_str!("fixtures/module.json");
+    assert_eq!(actual.trim_end(), expected.trim_end());
+}
+
+#[test]
+fn call_and_slice_expressions() {
+    use ast::Expr;
+
+    let module = parse_module("f(1, x=2, *a, **b)\n").expect("call expr should parse");
+    match &module[0].node {
+        ast::Statement::Expr(e) => match &e.node {
+            Expr::Call(_, call) => assert_eq!(call.args.len(), 4),
+            other => panic!("expected a call expression, got {:?}", other),
+        },
+        other => panic!("expected an expression statement, got {:?}", other),
+    }
+
+    let module = parse_module("a.b(1)[1:2:3]\n").expect("chained postfix expr should parse");
+    match &module[0].node {
+        ast::Statement::Expr(e) => assert!(matches!(e.node, Expr::Slice(..))),
+        other => panic!("expected an expression statement, got {:?}", other),
+    }
+}
+
+#[test]
+fn multi_target_assignment() {
+    use ast::Expr;
+
+    let module = parse_module("a, b = 1, 2\n").expect("unparenthesized tuple assign should parse");
+    match &module[0].node {
+        ast::Statement::Assign { targets, value } => {
+            assert_eq!(targets.len(), 1);
+            assert!(matches!(targets[0].node, Expr::Tuple(ref items) if items.len() == 2));
+            assert!(matches!(value.node, Expr::Tuple(ref items) if items.len() == 2));
+        }
+        other => panic!("expected an assign statement, got {:?}", other),
+    }
+
+    let module = parse_module("a, b = b, a\n").expect("swap assignment should parse");
+    assert!(matches!(&module[0].node, ast::Statement::Assign { .. }));
+
+    let module = parse_module("x = 1, 2\n").expect("bare tuple value should parse");
+    match &module[0].node {
+        ast::Statement::Assign { targets, value } => {
+            assert_eq!(targets.len(), 1);
+            assert!(matches!(targets[0].node, Expr::Identifier(_)));
+            assert!(matches!(value.node, Expr::Tuple(ref items) if items.len() == 2));
+        }
+        other => panic!("expected an assign statement, got {:?}", other),
+    }
+
+    let module = parse_module("a = b = c\n").expect("chained single-target assign should still parse");
+    match &module[0].node {
+        ast::Statement::Assign { targets, .. } => assert_eq!(targets.len(), 2),
+        other => panic!("expected an assign statement, got {:?}", other),
+    }
+}
+
+/// A one-line compound-statement body (`if x: pass`) goes through
+/// `SimpleStmt` rather than an indented `Suite::Statements` block; it
+/// should keep its span just the same.
+#[test]
+fn one_line_suite_keeps_spans() {
+    let module = parse_module("if x: pass\n").expect("one-line suite should parse");
+    match &module[0].node {
+        ast::Statement::If { branches, .. } => match &branches[0].1 {
+            ast::Suite::SimpleStmt(ast::SimpleStmt(stmts)) => {
+                assert_ne!((stmts[0].start, stmts[0].end), (0, 0));
+            }
+            other => panic!("expected a simple-statement suite, got {:?}", other),
+        },
+        other => panic!("expected an if statement, got {:?}", other),
+    }
 }
\ No newline at end of file